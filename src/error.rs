@@ -14,6 +14,36 @@ pub enum Error {
 
     #[error("JSON: {0}")]
     Json(serde_json::Error),
+
+    #[error("CSV: {0}")]
+    Csv(csv_async::Error),
+
+    #[error("Bincode: {0}")]
+    Bincode(bincode::Error),
+
+    #[error("TOML: {0}")]
+    Toml(toml::de::Error),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+}
+
+impl From<csv_async::Error> for Error {
+    fn from(value: csv_async::Error) -> Self {
+        Self::Csv(value)
+    }
+}
+
+impl From<bincode::Error> for Error {
+    fn from(value: bincode::Error) -> Self {
+        Self::Bincode(value)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(value: toml::de::Error) -> Self {
+        Self::Toml(value)
+    }
 }
 
 impl From<tokio::io::Error> for Error {
@@ -0,0 +1,173 @@
+use std::{
+    future::Future,
+    num::{NonZeroU64, NonZeroUsize},
+    sync::Arc,
+    time::Duration,
+};
+
+use indicatif::ProgressIterator;
+use rand::Rng;
+use tokio::{
+    io::AsyncWriteExt,
+    sync::Semaphore,
+    task,
+};
+
+use crate::{output::OutputWriter, Error, Joke};
+
+/// Tunables for [`retry`], exposed on `Scrape` as `--max-retries` and
+/// `--base-delay-ms`.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryConfig {
+    /// Maximum number of retries after the first attempt for a transient
+    /// failure before giving up, so at most `max_retries + 1` attempts.
+    pub max_retries: u32,
+    /// Base delay for exponential backoff, in milliseconds. Doubled on each
+    /// retry and jittered by up to 50% to avoid synchronized retries.
+    pub base_delay_ms: u64,
+}
+
+/// Returns `true` if `error` represents a permanent miss (an HTTP 404 or a
+/// "dirty" joke requiring sign-in) rather than a transient network error.
+/// Permanent misses are never worth retrying.
+pub fn is_permanent(error: &Error) -> bool {
+    matches!(error, Error::NotFound(_))
+}
+
+/// Retries `f` with exponential backoff and jitter until it succeeds,
+/// returns a permanent [`Error::NotFound`], or exhausts `config.max_retries`.
+pub async fn retry<F, Fut, T>(config: &RetryConfig, mut f: F) -> Result<T, Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    let mut attempt = 0;
+    loop {
+        match f().await {
+            Ok(value) => return Ok(value),
+            Err(error) if is_permanent(&error) => return Err(error),
+            Err(error) => {
+                if attempt >= config.max_retries {
+                    return Err(error);
+                }
+                let backoff = backoff_ms(config.base_delay_ms, attempt);
+                let jitter = rand::thread_rng().gen_range(0..=backoff / 2 + 1);
+                tokio::time::sleep(Duration::from_millis(backoff + jitter)).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Computes the exponential backoff delay for `attempt`, given `base_delay_ms`.
+/// The shift exponent is capped at 63 so a long run of failures (well past
+/// any sane `--max-retries`) can't overflow the `u64` shift and panic.
+fn backoff_ms(base_delay_ms: u64, attempt: u32) -> u64 {
+    base_delay_ms.saturating_mul(1u64 << attempt.min(63))
+}
+
+/// A semaphore-backed concurrency limiter shared by all in-flight fetches
+/// during a scrape run, so `--concurrency` bounds how many requests are
+/// outstanding at once regardless of batch size.
+pub fn limiter(concurrency: NonZeroUsize) -> Arc<Semaphore> {
+    Arc::new(Semaphore::new(concurrency.get()))
+}
+
+/// Drives an ID-keyed source (wocka.com, a config-driven [`crate::source`])
+/// to exhaustion: batches of `joke_tasks` IDs are fetched concurrently
+/// behind `limiter`, each retried per `retry_config`, and every success is
+/// serialized through `out` and written to `writer`. Stops once
+/// `max_consecutive_misses` permanent misses land in a row. This is the
+/// shared engine behind both the wocka.com and config-driven source loops
+/// in `scrape`, which otherwise only differ in how a single ID is fetched.
+pub async fn scrape_by_id<Fetch, FetchFut, J>(
+    label: &str,
+    joke_tasks: u16,
+    limiter: &Arc<Semaphore>,
+    retry_config: RetryConfig,
+    max_consecutive_misses: u32,
+    fetch_one: Fetch,
+    out: &mut dyn OutputWriter,
+    writer: &mut (impl tokio::io::AsyncWrite + Unpin),
+) -> Result<(), Error>
+where
+    Fetch: Fn(NonZeroU64) -> FetchFut + Clone + Send + 'static,
+    FetchFut: Future<Output = Result<J, Error>> + Send + 'static,
+    J: Into<Joke> + Send + 'static,
+{
+    let mut offset: u64 = 0;
+    let mut consecutive_misses = 0;
+    let mut buf = Vec::new();
+    println!("Starting \"{label}\"...");
+
+    'outer: loop {
+        let tasks = (1u16..=joke_tasks)
+            .map(|i| {
+                let limiter = limiter.clone();
+                let fetch_one = fetch_one.clone();
+                let retry_config = retry_config;
+                let id = NonZeroU64::new(i as u64 + offset).unwrap();
+                task::spawn(async move {
+                    let _permit = limiter.acquire_owned().await.unwrap();
+                    retry(&retry_config, || fetch_one(id)).await
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for task in tasks.into_iter().progress() {
+            match task.await? {
+                Ok(item) => {
+                    let joke: Joke = item.into();
+                    buf.clear();
+                    out.write_joke(&mut buf, &joke)?;
+                    writer.write_all(&buf).await?;
+                    consecutive_misses = 0;
+                }
+                Err(e) if is_permanent(&e) => {
+                    consecutive_misses += 1;
+                    if consecutive_misses >= max_consecutive_misses {
+                        break 'outer;
+                    }
+                }
+                Err(e) => eprintln!("\"{label}\": giving up on a joke: {e}"),
+            }
+        }
+        offset += joke_tasks as u64;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn backoff_ms_stays_capped_past_64_attempts() {
+        // Without the `.min(63)` cap this panics on overflow (debug builds)
+        // or silently wraps (release builds) once `attempt` reaches 64.
+        let capped = backoff_ms(1, 63);
+        assert_eq!(backoff_ms(1, 64), capped);
+        assert_eq!(backoff_ms(1, 1000), capped);
+    }
+
+    #[tokio::test]
+    async fn retry_does_not_panic_past_64_failures() {
+        let config = RetryConfig {
+            max_retries: 100,
+            base_delay_ms: 0,
+        };
+        let attempts = AtomicU32::new(0);
+
+        let result: Result<(), Error> = retry(&config, || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            async { Err(Error::Unhandled("always fails".into())) }
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), config.max_retries + 1);
+    }
+}
@@ -1,10 +1,10 @@
 #![feature(async_closure)]
 use std::{
+    net::SocketAddr,
     num::{NonZeroU64, NonZeroUsize, NonZeroU16},
     path::PathBuf,
     process::exit,
     sync::Arc,
-    time::Duration,
 };
 
 use csv::StringRecord;
@@ -18,18 +18,25 @@ use tokio::{
     task,
 };
 
-// mod reddit;
+mod db;
 mod error;
+mod fetch;
+mod output;
+mod reddit;
+#[cfg(feature = "serve")]
+mod serve;
+mod source;
 mod wocka;
+use output::Format;
 use error::Error;
 
 pub const PROGRAM_NAME: &str = env!("CARGO_PKG_NAME");
 pub const PROGRAM_VERSION: &str = env!("CARGO_PKG_VERSION");
 const OS: &str = std::env::consts::OS;
 
-const REDDIT_JOKE_PATH: &str = "../reddit_jokes.json";
 const STUPIDSTUFF_JOKE_PATH: &str = "../stupidstuff.json";
 const WOCKA_JOKE_PATH: &str = "wocka.csv";
+const DB_JOKE_PATH: &str = "jokes.db";
 const OUTPUT_JOKE_PATH: &str = "jokes_filtered.csv";
 const MINIMUM_REDDIT_UPVOTES: isize = 32;
 const MINIMUM_STUPIDSTUFF_RATING: f64 = 3.5;
@@ -37,7 +44,7 @@ const MINIMUM_STUPIDSTUFF_RATING: f64 = 3.5;
 #[derive(Debug, Clone, PartialEq, StructOpt)]
 enum Options {
     Scrape {
-        #[structopt(long, short, default_value = WOCKA_JOKE_PATH, name = "FILE")]
+        #[structopt(long, short, default_value = WOCKA_JOKE_PATH, value_name = "FILE")]
         output: PathBuf,
 
         #[structopt(long)]
@@ -46,10 +53,80 @@ enum Options {
         /// Jokes are scraped in batches, this is the size of a batch.
         #[structopt(short, long, default_value = "50")]
         tasks: NonZeroU16,
+
+        /// Path to a TOML file declaring additional, config-driven sources
+        /// (see `source::SourceConfig`). Scraped alongside wocka.com unless
+        /// `--no-wocka` is given.
+        #[structopt(long, value_name = "FILE")]
+        sources: Option<PathBuf>,
+
+        /// Maximum number of in-flight requests at any time, across the
+        /// whole batch.
+        #[structopt(long, default_value = "20")]
+        concurrency: NonZeroUsize,
+
+        /// Maximum retry attempts for a transient failure before it's
+        /// logged and skipped.
+        #[structopt(long, default_value = "5")]
+        max_retries: u32,
+
+        /// Base delay for exponential backoff between retries, in
+        /// milliseconds.
+        #[structopt(long, default_value = "200")]
+        base_delay_ms: u64,
+
+        /// Stop a source once this many *permanent* misses (404s, "dirty"
+        /// jokes) land in a row, taken as having reached the end of the ID
+        /// space. Transient failures don't count towards this.
+        #[structopt(long, default_value = "50")]
+        max_consecutive_misses: u32,
+
+        /// Also scrape `r/Jokes` live, following its `new.json` listing
+        /// instead of reading a static dump.
+        #[structopt(long)]
+        reddit: bool,
+
+        /// Stop following the reddit listing cursor after this many posts
+        /// have been seen, regardless of score.
+        #[structopt(long, default_value = "1000")]
+        reddit_limit: usize,
+
+        /// Output format: `csv`, `json`, `ndjson`, or (with the `rss`
+        /// feature) `rss`. Only `csv` output can be fed into `Build`.
+        #[structopt(long, default_value = "csv")]
+        format: Format,
     },
     Count {
-        #[structopt(long, short, default_value = WOCKA_JOKE_PATH, name = "FILE")]
+        #[structopt(long, short, default_value = WOCKA_JOKE_PATH, value_name = "FILE")]
+        input: PathBuf,
+    },
+    /// Converts a CSV joke file into the compressed, random-access binary
+    /// database format.
+    Build {
+        #[structopt(long, short, default_value = WOCKA_JOKE_PATH, value_name = "FILE")]
+        input: PathBuf,
+
+        #[structopt(long, short, default_value = DB_JOKE_PATH, value_name = "FILE")]
+        output: PathBuf,
+    },
+    /// Fetches a single joke by record index from a binary database built
+    /// with `Build`.
+    Get {
+        #[structopt(long, short, default_value = DB_JOKE_PATH, value_name = "FILE")]
         input: PathBuf,
+
+        #[structopt(long, short = "d")]
+        id: u64,
+    },
+    /// Serves a binary database built with `Build` over a small JSON HTTP
+    /// API (`GET /random`, `GET /id/:id`, `GET /search?q=`).
+    #[cfg(feature = "serve")]
+    Serve {
+        #[structopt(long, short, default_value = DB_JOKE_PATH, value_name = "FILE")]
+        input: PathBuf,
+
+        #[structopt(long, default_value = "127.0.0.1:3000")]
+        addr: SocketAddr,
     },
 }
 
@@ -78,10 +155,10 @@ struct StupidstuffJoke {
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
-struct Joke {
-    footer: String,
-    body: String,
-    title: String,
+pub(crate) struct Joke {
+    pub(crate) footer: String,
+    pub(crate) body: String,
+    pub(crate) title: String,
 }
 
 impl From<WockaJoke> for Joke {
@@ -123,45 +200,86 @@ impl From<StupidstuffJoke> for Joke {
     }
 }
 
-async fn scrape(output: PathBuf, no_wocka: bool, joke_tasks: u16) -> Result<(), Error> {
-    let joke_file_writer = BufWriter::new(File::create(output).await?);
-    let mut csv_serializer = csv::AsyncSerializer::from_writer(joke_file_writer);
-    let mut offset: u64 = 0;
-    let mut errors = 0;
-    println!("Starting...");
-    loop {
-        let tasks = (1 as u16..=joke_tasks)
-            .into_iter()
-            .map(|i| {
-                task::spawn(async move {
-                    wocka::extract_joke(NonZeroU64::new(i as u64 + offset).unwrap()).await
-                })
-            })
-            .collect::<Vec<_>>()
-            .into_iter();
-
-        for task in tasks.progress() {
-            match task.await.unwrap() {
-                Ok(x) => {
-                    let joke: Joke = x.into();
-                    // let json = task::spawn_blocking(move|| serde_json::to_string(&joke)).await??;
-                    csv_serializer.serialize(&joke).await.unwrap();
-                    // let x = joke_file_writer.write_all(json.as_bytes()).await?;
-                    errors = 0;
-                }
-                Err(e) => {
-                    errors += 1;
-                }
-            };
-            // Sleep for a bit to maybe not get blocked.
-            tokio::time::sleep(Duration::from_millis(10)).await;
+/// Knobs for a single `scrape` run, gathered from `Options::Scrape` so the
+/// flow below takes one parameter instead of one per flag.
+struct ScrapeConfig {
+    output: PathBuf,
+    no_wocka: bool,
+    joke_tasks: u16,
+    sources: Option<PathBuf>,
+    concurrency: NonZeroUsize,
+    max_retries: u32,
+    base_delay_ms: u64,
+    max_consecutive_misses: u32,
+    reddit: bool,
+    reddit_limit: usize,
+    format: Format,
+}
+
+async fn scrape(config: ScrapeConfig) -> Result<(), Error> {
+    let mut joke_file_writer = BufWriter::new(File::create(config.output).await?);
+    let mut out = output::writer(config.format);
+    let mut buf = Vec::new();
+    out.write_header(&mut buf)?;
+    joke_file_writer.write_all(&buf).await?;
+    let retry_config = fetch::RetryConfig {
+        max_retries: config.max_retries,
+        base_delay_ms: config.base_delay_ms,
+    };
+    let limiter = fetch::limiter(config.concurrency);
+
+    if !config.no_wocka {
+        fetch::scrape_by_id(
+            "wocka.com",
+            config.joke_tasks,
+            &limiter,
+            retry_config,
+            config.max_consecutive_misses,
+            wocka::extract_joke,
+            out.as_mut(),
+            &mut joke_file_writer,
+        )
+        .await?;
+    }
+
+    if let Some(sources_path) = config.sources {
+        let sources_file = source::load_sources(&sources_path).await?;
+        for (name, source_config) in sources_file.sources {
+            fetch::scrape_by_id(
+                &name,
+                config.joke_tasks,
+                &limiter,
+                retry_config,
+                config.max_consecutive_misses,
+                move |id| {
+                    let source_config = source_config.clone();
+                    let name = name.clone();
+                    async move { source::extract(&source_config, &name, id).await }
+                },
+                out.as_mut(),
+                &mut joke_file_writer,
+            )
+            .await?;
         }
-        if errors > 2000 {
-            break;
+    }
+
+    if config.reddit {
+        println!("Starting reddit...");
+        let client = reddit::client()?;
+        let jokes = reddit::scrape(&client, MINIMUM_REDDIT_UPVOTES, config.reddit_limit).await?;
+        for joke in jokes.into_iter().progress() {
+            let joke: Joke = joke.into();
+            buf.clear();
+            out.write_joke(&mut buf, &joke)?;
+            joke_file_writer.write_all(&buf).await?;
         }
-        offset += joke_tasks as u64;
     }
 
+    buf.clear();
+    out.write_footer(&mut buf)?;
+    joke_file_writer.write_all(&buf).await?;
+    joke_file_writer.flush().await?;
+
     Ok(())
 }
 
@@ -174,7 +292,30 @@ async fn main() -> Result<(), anyhow::Error> {
             output,
             no_wocka,
             tasks,
-        } => scrape(output, no_wocka, tasks.into()).await?,
+            sources,
+            concurrency,
+            max_retries,
+            base_delay_ms,
+            max_consecutive_misses,
+            reddit,
+            reddit_limit,
+            format,
+        } => {
+            scrape(ScrapeConfig {
+                output,
+                no_wocka,
+                joke_tasks: tasks.into(),
+                sources,
+                concurrency,
+                max_retries,
+                base_delay_ms,
+                max_consecutive_misses,
+                reddit,
+                reddit_limit,
+                format,
+            })
+            .await?
+        }
         Options::Count { input } => {
             let joke_file_reader = BufReader::new(File::open(&input).await?);
             let mut csv_deseraializer = csv::AsyncDeserializer::from_reader(joke_file_reader);
@@ -185,11 +326,17 @@ async fn main() -> Result<(), anyhow::Error> {
             }
             println!("\"{}\" has {rows} jokes.", input.display());
         }
+        Options::Build { input, output } => db::build(input, output).await?,
+        Options::Get { input, id } => {
+            let joke = db::get(input, id).await?;
+            println!("{}\n\n{}\n{}", joke.title, joke.body, joke.footer);
+        }
+        #[cfg(feature = "serve")]
+        Options::Serve { input, addr } => serve::run(input, addr).await?,
     }
 
     // println!("Filtering...");
-    // let mut jokes = filter_reddit().await;
-    // jokes.append(&mut filter_stupidstuff().await?);
+    // let mut jokes = filter_stupidstuff().await?;
     // jokes.append(&mut filter_wocka().await);
     // let joke_file_writer = BufWriter::new(File::create(OUTPUT_JOKE_PATH).await?);
     // let mut csv_serializer = csv::AsyncSerializer::from_writer(joke_file_writer);
@@ -225,31 +372,6 @@ async fn filter_stupidstuff() -> Result<Vec<Joke>, Error> {
     .await)
 }
 
-async fn filter_reddit() -> Vec<Joke> {
-    let reddit_jokes_data = fs::read(REDDIT_JOKE_PATH).await.unwrap();
-    let reddit_jokes = task::spawn_blocking(move || {
-        serde_json::from_slice::<Vec<RedditJoke>>(&reddit_jokes_data).unwrap()
-    })
-    .await
-    .unwrap();
-
-    tokio_rayon::spawn(move || {
-        // Filter out the following jokes:
-        //
-        // - Jokes with less than `MINIMUM_REDDIT_UPVOTES` score
-        reddit_jokes
-            .into_iter()
-            .progress()
-            .filter(|joke| joke.score >= MINIMUM_REDDIT_UPVOTES)
-            .map(|x| {
-                let x: Joke = x.into();
-                x
-            })
-            .collect::<Vec<_>>()
-    })
-    .await
-}
-
 async fn filter_wocka() -> Vec<Joke> {
     let jokes_data = fs::read(WOCKA_JOKE_PATH).await.unwrap();
     let jokes = task::spawn_blocking(move || {
@@ -0,0 +1,162 @@
+use std::str::FromStr;
+
+use crate::{Error, Joke};
+
+/// Output format selected via `--format` on `Scrape`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Json,
+    Ndjson,
+    #[cfg(feature = "rss")]
+    Rss,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            "ndjson" => Ok(Self::Ndjson),
+            #[cfg(feature = "rss")]
+            "rss" => Ok(Self::Rss),
+            other => Err(format!("unknown output format \"{other}\"")),
+        }
+    }
+}
+
+/// Builds the [`OutputWriter`] for `format`.
+pub fn writer(format: Format) -> Box<dyn OutputWriter + Send> {
+    match format {
+        Format::Csv => Box::new(CsvWriter::default()),
+        Format::Json => Box::new(JsonWriter::default()),
+        Format::Ndjson => Box::new(NdjsonWriter),
+        #[cfg(feature = "rss")]
+        Format::Rss => Box::new(RssWriter::default()),
+    }
+}
+
+/// Serializes [`Joke`]s one at a time into a caller-owned byte buffer,
+/// which the caller writes to the output file after each call. `write_header`/`write_footer`
+/// only matter for formats with a document preamble/trailer (a JSON array's
+/// `[`/`]`, an RSS `<channel>`); formats without one use the default no-op.
+pub trait OutputWriter {
+    fn write_header(&mut self, _buf: &mut Vec<u8>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn write_joke(&mut self, buf: &mut Vec<u8>, joke: &Joke) -> Result<(), Error>;
+
+    fn write_footer(&mut self, _buf: &mut Vec<u8>) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Matches the column order `Joke`'s field declaration already produces
+/// via `csv_async`, so existing `wocka.csv` files stay compatible.
+#[derive(Default)]
+struct CsvWriter {
+    wrote_header: bool,
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+impl OutputWriter for CsvWriter {
+    fn write_joke(&mut self, buf: &mut Vec<u8>, joke: &Joke) -> Result<(), Error> {
+        if !self.wrote_header {
+            buf.extend_from_slice(b"footer,body,title\n");
+            self.wrote_header = true;
+        }
+        let row = format!(
+            "{},{},{}\n",
+            csv_field(&joke.footer),
+            csv_field(&joke.body),
+            csv_field(&joke.title)
+        );
+        buf.extend_from_slice(row.as_bytes());
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct JsonWriter {
+    wrote_any: bool,
+}
+
+impl OutputWriter for JsonWriter {
+    fn write_header(&mut self, buf: &mut Vec<u8>) -> Result<(), Error> {
+        buf.extend_from_slice(b"[\n");
+        Ok(())
+    }
+
+    fn write_joke(&mut self, buf: &mut Vec<u8>, joke: &Joke) -> Result<(), Error> {
+        if self.wrote_any {
+            buf.extend_from_slice(b",\n");
+        }
+        for line in serde_json::to_string_pretty(joke)?.lines() {
+            buf.extend_from_slice(b"  ");
+            buf.extend_from_slice(line.as_bytes());
+            buf.push(b'\n');
+        }
+        self.wrote_any = true;
+        Ok(())
+    }
+
+    fn write_footer(&mut self, buf: &mut Vec<u8>) -> Result<(), Error> {
+        buf.extend_from_slice(b"]\n");
+        Ok(())
+    }
+}
+
+struct NdjsonWriter;
+
+impl OutputWriter for NdjsonWriter {
+    fn write_joke(&mut self, buf: &mut Vec<u8>, joke: &Joke) -> Result<(), Error> {
+        serde_json::to_writer(&mut *buf, joke)?;
+        buf.push(b'\n');
+        Ok(())
+    }
+}
+
+/// The `rss` crate only implements `write_to` (XML serialization) on
+/// `Channel`, not `Item`, so unlike the other writers this one can't stream
+/// each joke straight to disk: it accumulates `Item`s and only produces XML
+/// once, in `write_footer`, by building a `Channel` around them.
+#[cfg(feature = "rss")]
+#[derive(Default)]
+struct RssWriter {
+    items: Vec<rss::Item>,
+}
+
+#[cfg(feature = "rss")]
+impl OutputWriter for RssWriter {
+    fn write_joke(&mut self, _buf: &mut Vec<u8>, joke: &Joke) -> Result<(), Error> {
+        self.items.push(
+            rss::ItemBuilder::default()
+                .title(Some(joke.title.clone()))
+                .description(Some(joke.body.clone()))
+                .content(Some(joke.footer.clone()))
+                .build(),
+        );
+        Ok(())
+    }
+
+    fn write_footer(&mut self, buf: &mut Vec<u8>) -> Result<(), Error> {
+        let channel = rss::ChannelBuilder::default()
+            .title("jokeset")
+            .items(std::mem::take(&mut self.items))
+            .build();
+        channel
+            .write_to(&mut *buf)
+            .map_err(|e| Error::Unhandled(format!("RSS: {e}")))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::{Error, RedditJoke};
+
+const LISTING_URL: &str = "https://www.reddit.com/r/Jokes/new.json";
+const USER_AGENT: &str = concat!(
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION"),
+    " (joke scraper)"
+);
+
+#[derive(Debug, Deserialize)]
+struct Listing {
+    data: ListingData,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListingData {
+    after: Option<String>,
+    children: Vec<ListingChild>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListingChild {
+    data: PostData,
+}
+
+#[derive(Debug, Deserialize)]
+struct PostData {
+    id: String,
+    title: String,
+    #[serde(default)]
+    selftext: String,
+    score: isize,
+}
+
+impl From<PostData> for RedditJoke {
+    fn from(value: PostData) -> Self {
+        Self {
+            id: value.id,
+            score: value.score,
+            title: value.title,
+            body: value.selftext,
+        }
+    }
+}
+
+/// Builds the shared [`Client`] used for listing requests, identified by a
+/// proper `User-Agent` as Reddit's API requires.
+pub fn client() -> Result<Client, Error> {
+    Client::builder()
+        .user_agent(USER_AGENT)
+        .build()
+        .map_err(Error::from)
+}
+
+/// Scrapes `r/Jokes` via its public `.json` listing endpoint, following the
+/// `after` fullname cursor until the listing is exhausted or `post_limit`
+/// posts have been seen. Only posts scoring at least `min_upvotes` are
+/// returned.
+pub async fn scrape(
+    client: &Client,
+    min_upvotes: isize,
+    post_limit: usize,
+) -> Result<Vec<RedditJoke>, Error> {
+    let mut jokes = Vec::new();
+    let mut after: Option<String> = None;
+    let mut seen = 0;
+
+    loop {
+        let mut request = client.get(LISTING_URL).query(&[("limit", "100")]);
+        if let Some(after) = &after {
+            request = request.query(&[("after", after)]);
+        }
+        let listing: Listing = request.send().await?.json().await?;
+
+        if listing.data.children.is_empty() {
+            break;
+        }
+
+        for child in listing.data.children {
+            seen += 1;
+            if child.data.score >= min_upvotes {
+                jokes.push(child.data.into());
+            }
+            if seen >= post_limit {
+                return Ok(jokes);
+            }
+        }
+
+        after = listing.data.after;
+        if after.is_none() {
+            break;
+        }
+    }
+
+    Ok(jokes)
+}
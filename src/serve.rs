@@ -0,0 +1,93 @@
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::{db, Error, Joke};
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            Error::NotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, self.to_string()).into_response()
+    }
+}
+
+struct AppState {
+    db_path: PathBuf,
+    count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchQuery {
+    q: String,
+}
+
+/// Binds `addr` and serves the joke corpus at `db_path` (built with
+/// `Options::Build`) over a small JSON HTTP API: `GET /random`,
+/// `GET /id/:id`, and `GET /search?q=`.
+pub async fn run(db_path: PathBuf, addr: SocketAddr) -> Result<(), Error> {
+    let count = db::count(&db_path).await?;
+    let state = Arc::new(AppState { db_path, count });
+
+    let app = Router::new()
+        .route("/random", get(random))
+        .route("/id/:id", get(by_id))
+        .route("/search", get(search))
+        .with_state(state);
+
+    println!("Serving {count} jokes on http://{addr}");
+    axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .await
+        .map_err(|e| Error::Unhandled(format!("Server: {e}")))
+}
+
+async fn random(State(state): State<Arc<AppState>>) -> Result<Json<Joke>, Error> {
+    if state.count == 0 {
+        return Err(Error::NotFound("corpus is empty".into()));
+    }
+    let id = rand::thread_rng().gen_range(0..state.count);
+    let joke = db::get(state.db_path.clone(), id).await?;
+    Ok(Json(joke))
+}
+
+async fn by_id(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<u64>,
+) -> Result<Json<Joke>, Error> {
+    let joke = db::get(state.db_path.clone(), id).await?;
+    Ok(Json(joke))
+}
+
+/// Scans every record for one whose title or body contains `q`
+/// (case-insensitively), capped at 50 results. Uses `db::scan` so the file
+/// is opened and its header parsed once for the whole search, rather than
+/// once per candidate record the way a loop of `db::get` would.
+async fn search(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<Vec<Joke>>, Error> {
+    let needle = query.q.to_lowercase();
+    let mut matches = Vec::new();
+
+    db::scan(&state.db_path, |_id, joke| {
+        if joke.title.to_lowercase().contains(&needle) || joke.body.to_lowercase().contains(&needle)
+        {
+            matches.push(joke.clone());
+        }
+        matches.len() < 50
+    })
+    .await?;
+
+    Ok(Json(matches))
+}
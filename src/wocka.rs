@@ -16,16 +16,22 @@ lazy_static! {
 /// it cannot be parsed and `None` is returned.
 pub async fn extract_joke(id: NonZeroU64) -> Result<WockaJoke, Error> {
     let url = format!("http://www.wocka.com/{id}.html");
-    let response = reqwest::get(&url).await?.text().await?;
+    let response = reqwest::get(&url).await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(Error::NotFound(format!("wocka.com joke {id} (404)")));
+    }
+    let response = response.text().await?;
     // println!("{response}");
     let document = Html::parse_document(&response);
 
     // Get the title of the joke, it's the first (and only level-two heading on the page).
+    // A missing title means the joke doesn't exist or is "dirty" (requires
+    // sign-in), neither of which is worth retrying.
     let title = document
         .select(&TITLE_SELECTOR)
         .next()
         .and_then(|x| x.text().next())
-        .ok_or(Error::Unhandled("Malformed wocka.com HTML".into()))?
+        .ok_or_else(|| Error::NotFound(format!("wocka.com joke {id} (missing or dirty)")))?
         .to_string();
 
     let joke_details_table = document
@@ -47,12 +53,13 @@ pub async fn extract_joke(id: NonZeroU64) -> Result<WockaJoke, Error> {
     let category_position = joke_details_table
         .iter()
         .position(|x| x == "Category")
-        .ok_or(Error::Unhandled("Malformed wocka.com HTML".into()))?
+        .ok_or_else(|| Error::NotFound(format!("wocka.com joke {id} (missing or dirty)")))?
         + 1;
 
     let category = joke_details_table
         .get(category_position)
-        .ok_or(Error::Unhandled("Malformed wocka.com HTML".into()))?.to_owned();
+        .ok_or_else(|| Error::NotFound(format!("wocka.com joke {id} (missing or dirty)")))?
+        .to_owned();
 
     // Select `div#content`, actually grab it's child nodes instead.
     // Filter out all but text nodes. Text nodes are trimmed. Then collect,
@@ -61,7 +68,7 @@ pub async fn extract_joke(id: NonZeroU64) -> Result<WockaJoke, Error> {
         .select(&CONTENT_SELECTOR)
         .map(|x| x.children())
         .next()
-        .ok_or(Error::Unhandled("Malformed wocka.com HTML".into()))?
+        .ok_or_else(|| Error::NotFound(format!("wocka.com joke {id} (missing or dirty)")))?
         .filter_map(|x| x.value().as_text().and_then(|x| Some(x.to_string())))
         .filter_map(|x| {
             let x = x.trim();
@@ -74,7 +81,7 @@ pub async fn extract_joke(id: NonZeroU64) -> Result<WockaJoke, Error> {
         .collect::<Vec<_>>()
         .join("\n");
     if body.is_empty() {
-        return Err(Error::Unhandled("Malformed wocka.com HTML".into()));
+        return Err(Error::NotFound(format!("wocka.com joke {id} (missing or dirty)")));
     }
 
     Ok(WockaJoke {